@@ -10,6 +10,10 @@ pub struct MemStats {
     pub alloc_count: AtomicUsize,
     /// Number of bytes allocated.
     pub allocated: AtomicUsize,
+    /// Number of bytes currently live (allocated but not yet freed).
+    pub live: AtomicUsize,
+    /// High-water mark of `live`, updated with a compare-and-swap max on every allocation.
+    pub peak: AtomicUsize,
 }
 
 impl Clone for MemStats {
@@ -17,6 +21,8 @@ impl Clone for MemStats {
         Self {
             alloc_count: AtomicUsize::new(self.alloc_count.load(Ordering::Relaxed)),
             allocated: AtomicUsize::new(self.allocated.load(Ordering::Relaxed)),
+            live: AtomicUsize::new(self.live.load(Ordering::Relaxed)),
+            peak: AtomicUsize::new(self.peak.load(Ordering::Relaxed)),
         }
     }
 }
@@ -27,6 +33,8 @@ impl std::ops::Div<usize> for MemStats {
         Self {
             alloc_count: AtomicUsize::new(self.alloc_count.load(Ordering::Relaxed) / rhs),
             allocated: AtomicUsize::new(self.allocated.load(Ordering::Relaxed) / rhs),
+            live: AtomicUsize::new(self.live.load(Ordering::Relaxed) / rhs),
+            peak: AtomicUsize::new(self.peak.load(Ordering::Relaxed) / rhs),
         }
     }
 }
@@ -41,6 +49,19 @@ impl std::ops::Sub for MemStats {
             allocated: AtomicUsize::new(
                 self.allocated.load(Ordering::Relaxed) - rhs.allocated.load(Ordering::Relaxed),
             ),
+            // Unlike `alloc_count`/`allocated`, `live` isn't monotonic: a window can free more
+            // than it allocates (e.g. the benchmarked value is dropped before the timer
+            // stops), so floor the delta at zero instead of underflowing.
+            live: AtomicUsize::new(
+                self.live
+                    .load(Ordering::Relaxed)
+                    .saturating_sub(rhs.live.load(Ordering::Relaxed)),
+            ),
+            peak: AtomicUsize::new(
+                self.peak
+                    .load(Ordering::Relaxed)
+                    .saturating_sub(rhs.peak.load(Ordering::Relaxed)),
+            ),
         }
     }
 }
@@ -59,6 +80,8 @@ impl<Alloc: GlobalAlloc> CountingAllocator<Alloc> {
             stats: MemStats {
                 alloc_count: AtomicUsize::new(0),
                 allocated: AtomicUsize::new(0),
+                live: AtomicUsize::new(0),
+                peak: AtomicUsize::new(0),
             },
         }
     }
@@ -66,6 +89,13 @@ impl<Alloc: GlobalAlloc> CountingAllocator<Alloc> {
     pub fn stats(&self) -> &MemStats {
         &self.stats
     }
+    /// Resets the high-water mark to the number of bytes currently live, so a subsequent
+    /// measurement window's `peak` is not raised by highs reached before this point. The
+    /// bytes already live at this point still count towards it.
+    pub fn reset_peak(&self) {
+        let live = self.stats.live.load(Ordering::Relaxed);
+        self.stats.peak.store(live, Ordering::Relaxed);
+    }
 }
 
 unsafe impl<Alloc: GlobalAlloc> GlobalAlloc for CountingAllocator<Alloc> {
@@ -76,10 +106,60 @@ unsafe impl<Alloc: GlobalAlloc> GlobalAlloc for CountingAllocator<Alloc> {
             self.stats
                 .allocated
                 .fetch_add(layout.size(), Ordering::Relaxed);
+            let live = self.stats.live.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            self.stats.peak.fetch_max(live, Ordering::Relaxed);
         }
         ptr
     }
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
         self.inner.dealloc(ptr, layout);
+        self.stats.live.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::alloc::System;
+
+    #[test]
+    fn live_tracks_allocations_and_deallocations() {
+        let allocator = CountingAllocator::new(System);
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        unsafe {
+            let ptr = allocator.alloc(layout);
+            assert_eq!(allocator.stats().live.load(Ordering::Relaxed), 64);
+            allocator.dealloc(ptr, layout);
+            assert_eq!(allocator.stats().live.load(Ordering::Relaxed), 0);
+        }
+    }
+
+    #[test]
+    fn peak_holds_the_high_water_mark_after_deallocation() {
+        let allocator = CountingAllocator::new(System);
+        let small = Layout::from_size_align(16, 8).unwrap();
+        let big = Layout::from_size_align(64, 8).unwrap();
+        unsafe {
+            let small_ptr = allocator.alloc(small);
+            let big_ptr = allocator.alloc(big);
+            assert_eq!(allocator.stats().peak.load(Ordering::Relaxed), 80);
+            allocator.dealloc(big_ptr, big);
+            // Live drops, but peak remembers the high-water mark.
+            assert_eq!(allocator.stats().live.load(Ordering::Relaxed), 16);
+            assert_eq!(allocator.stats().peak.load(Ordering::Relaxed), 80);
+            allocator.dealloc(small_ptr, small);
+        }
+    }
+
+    #[test]
+    fn reset_peak_floors_it_at_current_live() {
+        let allocator = CountingAllocator::new(System);
+        let layout = Layout::from_size_align(32, 8).unwrap();
+        unsafe {
+            let ptr = allocator.alloc(layout);
+            allocator.reset_peak();
+            assert_eq!(allocator.stats().peak.load(Ordering::Relaxed), 32);
+            allocator.dealloc(ptr, layout);
+        }
     }
 }
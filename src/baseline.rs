@@ -0,0 +1,201 @@
+//! Persisting benchmark runs to disk and comparing later runs against them, so gbenchmark can
+//! be used to catch regressions in CI.
+
+use std::{fmt::Display, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::measure::percentile;
+
+/// A saved snapshot of a benchmark run, keyed by benchmark name, suitable for comparing future
+/// runs against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Name of the benchmark this baseline was recorded for.
+    pub name: String,
+    /// Point estimate (ns/op) recorded for this baseline.
+    pub estimate: f64,
+    /// Sorted bootstrap resample distribution backing `estimate`, if available (see
+    /// [`crate::measure::StatsMeasure`]). Used to test whether a later run's change is
+    /// statistically significant rather than noise.
+    pub resamples: Option<Vec<f64>>,
+}
+
+impl Baseline {
+    /// Creates a new baseline with no bootstrap distribution attached.
+    pub fn new(name: impl Into<String>, estimate: f64) -> Self {
+        Self {
+            name: name.into(),
+            estimate,
+            resamples: None,
+        }
+    }
+    /// Attaches a sorted bootstrap resample distribution so later comparisons can test for
+    /// statistical significance.
+    pub fn with_resamples(mut self, resamples: Vec<f64>) -> Self {
+        self.resamples = Some(resamples);
+        self
+    }
+    /// Loads the baseline named `name` from the baselines stored at `path`, if any.
+    pub fn load(path: impl AsRef<Path>, name: &str) -> io::Result<Option<Self>> {
+        Ok(Self::load_all(path)?.into_iter().find(|b| b.name == name))
+    }
+    /// Saves this baseline to `path`, replacing any existing entry with the same name.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut baselines = Self::load_all(&path)?;
+        baselines.retain(|b| b.name != self.name);
+        baselines.push(self.clone());
+        let json = serde_json::to_string_pretty(&baselines)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json)
+    }
+    fn load_all(path: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Outcome of comparing a new run's estimate to a saved [`Baseline`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The new estimate is worse than the baseline by more than the noise threshold, and the
+    /// change was confirmed significant where a bootstrap distribution was available.
+    Regression,
+    /// The new estimate is better than the baseline by more than the noise threshold.
+    Improvement,
+    /// The new estimate is within the noise threshold of the baseline, or the change could not
+    /// be confirmed significant.
+    NoChange,
+}
+
+/// The result of comparing a new benchmark run against a [`Baseline`].
+pub struct Comparison {
+    /// Baseline estimate, in ns/op.
+    pub baseline: f64,
+    /// New estimate, in ns/op.
+    pub current: f64,
+    /// Relative change from baseline to current, e.g. `0.05` for a 5% slowdown.
+    pub relative_change: f64,
+    /// The verdict reached.
+    pub verdict: Verdict,
+}
+
+impl Comparison {
+    /// Compares `current` (with an optional sorted bootstrap distribution) to `baseline`.
+    ///
+    /// A relative change smaller than `noise_threshold` is always reported as [`Verdict::NoChange`].
+    /// A larger change is only flagged as a regression or improvement if the two sides'
+    /// confidence intervals at `significance_level` (computed from their bootstrap
+    /// distributions) don't overlap; when either side has no distribution, the noise threshold
+    /// alone decides.
+    pub fn compare(
+        baseline: &Baseline,
+        current: f64,
+        current_resamples: Option<&[f64]>,
+        noise_threshold: f64,
+        significance_level: f64,
+    ) -> Self {
+        let relative_change = (current - baseline.estimate) / baseline.estimate;
+        let mut verdict = if relative_change.abs() < noise_threshold {
+            Verdict::NoChange
+        } else if relative_change > 0.0 {
+            Verdict::Regression
+        } else {
+            Verdict::Improvement
+        };
+        if verdict != Verdict::NoChange {
+            if let (Some(baseline_resamples), Some(current_resamples)) =
+                (baseline.resamples.as_deref(), current_resamples)
+            {
+                if cis_overlap(baseline_resamples, current_resamples, significance_level) {
+                    verdict = Verdict::NoChange;
+                }
+            }
+        }
+        Self {
+            baseline: baseline.estimate,
+            current,
+            relative_change,
+            verdict,
+        }
+    }
+    /// Returns true if the comparison found a regression, so a CI harness can fail the build.
+    pub fn is_regression(&self) -> bool {
+        self.verdict == Verdict::Regression
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self.verdict {
+            Verdict::Regression => "regression",
+            Verdict::Improvement => "improvement",
+            Verdict::NoChange => "no change",
+        };
+        write!(
+            f,
+            "{}: {:+.2}% ({:.3} -> {:.3} ns/op)",
+            label,
+            self.relative_change * 100.0,
+            self.baseline,
+            self.current
+        )
+    }
+}
+
+/// Checks whether the `significance_level` confidence intervals of two sorted bootstrap
+/// distributions overlap.
+fn cis_overlap(a: &[f64], b: &[f64], significance_level: f64) -> bool {
+    let (a_lo, a_hi) = ci(a, significance_level);
+    let (b_lo, b_hi) = ci(b, significance_level);
+    a_lo <= b_hi && b_lo <= a_hi
+}
+
+fn ci(sorted_resamples: &[f64], significance_level: f64) -> (f64, f64) {
+    (
+        percentile(sorted_resamples, significance_level / 2.0),
+        percentile(sorted_resamples, 1.0 - significance_level / 2.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_flags_regression_beyond_noise_threshold() {
+        let baseline = Baseline::new("bench", 100.0);
+        let comparison = Comparison::compare(&baseline, 120.0, None, 0.02, 0.05);
+        assert_eq!(comparison.verdict, Verdict::Regression);
+        assert!(comparison.is_regression());
+    }
+
+    #[test]
+    fn compare_flags_improvement_beyond_noise_threshold() {
+        let baseline = Baseline::new("bench", 100.0);
+        let comparison = Comparison::compare(&baseline, 80.0, None, 0.02, 0.05);
+        assert_eq!(comparison.verdict, Verdict::Improvement);
+        assert!(!comparison.is_regression());
+    }
+
+    #[test]
+    fn compare_reports_no_change_within_noise_threshold() {
+        let baseline = Baseline::new("bench", 100.0);
+        let comparison = Comparison::compare(&baseline, 101.0, None, 0.02, 0.05);
+        assert_eq!(comparison.verdict, Verdict::NoChange);
+        assert!(!comparison.is_regression());
+    }
+
+    #[test]
+    fn compare_downgrades_to_no_change_when_cis_overlap() {
+        let baseline = Baseline::new("bench", 100.0).with_resamples(vec![95.0, 100.0, 105.0]);
+        // Current's distribution overlaps the baseline's despite a > 2% point-estimate shift.
+        let current_resamples = vec![98.0, 104.0, 110.0];
+        let comparison = Comparison::compare(&baseline, 106.0, Some(&current_resamples), 0.02, 0.5);
+        assert_eq!(comparison.verdict, Verdict::NoChange);
+    }
+}
@@ -43,12 +43,18 @@
 //!
 #![warn(missing_docs)]
 
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
 
 use measure::Measure;
 
 /// The [alloc] module contains an allocator wrapper that counts allocations.
 pub mod alloc;
+/// The [baseline] module supports saving benchmark runs to disk and comparing later runs
+/// against them, for catching regressions in CI.
+pub mod baseline;
 /// The [measure] module contains the `Measure` trait and some implementations.
 pub mod measure;
 
@@ -94,8 +100,32 @@ where
         }
     }
     /// Benchmark a function to [Measure]'s satisfaction.
+    ///
+    /// If the measure requests a warm-up (see [`Measure::warm_up`]), the function is first run
+    /// in a loop for at least that long with all timings discarded, and the warm-up is also
+    /// used to calibrate the initial repetition count so the first real measurement batch runs
+    /// for a sensible minimum duration instead of always starting at a single repetition.
     pub fn benchmark<F: FnMut(&P, &mut dyn FnMut())>(&self, f: &mut F) -> BenchmarkResult<P, M> {
         let mut params = (self.params_factory)();
+        let warm_up = (self.measure_factory)().warm_up();
+        if !warm_up.is_zero() {
+            let mut elapsed = Duration::ZERO;
+            let mut total_reps: usize = 0;
+            while elapsed < warm_up {
+                let start = Instant::now();
+                f(&params, &mut || {});
+                elapsed += start.elapsed();
+                total_reps += params.nreps();
+                if elapsed < warm_up {
+                    params.more();
+                }
+            }
+            let ns_per_rep = elapsed.as_nanos() as f64 / total_reps as f64;
+            if ns_per_rep > 0.0 {
+                let target_nreps = (warm_up.as_nanos() as f64 / ns_per_rep).max(1.0) as usize;
+                params.calibrate(target_nreps);
+            }
+        }
         loop {
             let mut result = (self.measure_factory)();
             result.observe(&mut *f, &params);
@@ -108,6 +138,19 @@ where
             params.more();
         }
     }
+    /// Runs the target function in a tight loop for `duration`, taking no measurements and
+    /// never invoking the measure's `enough`/[`Params::more`] scaling logic.
+    ///
+    /// This is meant for running a benchmark under an external profiler (perf, valgrind,
+    /// samply): it avoids gbenchmark's own measurement and auto-scaling overhead polluting the
+    /// profile, and keeps total runtime roughly constant regardless of profiler overhead.
+    pub fn profile<F: FnMut(&P, &mut dyn FnMut())>(&self, duration: Duration, f: &mut F) {
+        let params = (self.params_factory)();
+        let start = Instant::now();
+        while start.elapsed() < duration {
+            f(&params, &mut || {});
+        }
+    }
 }
 
 /// Trait for benchmark parameters.
@@ -116,6 +159,9 @@ pub trait Params {
     fn nreps(&self) -> usize;
     /// [Measure] asked for more repetitions.
     fn more(&mut self);
+    /// Sets the initial repetition count from a warm-up measurement. Default: no-op, for
+    /// parameter types that don't have a meaningful notion of repetition count.
+    fn calibrate(&mut self, _nreps: usize) {}
 }
 
 /// A simple parameter that doubles the number of repetitions each time.
@@ -143,4 +189,7 @@ impl Params for RepetitionParams {
     fn more(&mut self) {
         self.nreps *= 2;
     }
+    fn calibrate(&mut self, nreps: usize) {
+        self.nreps = nreps.max(1);
+    }
 }
@@ -15,11 +15,96 @@ pub trait Measure<P: Params>: Display {
     fn enough(&self, params: &P) -> bool;
     /// Observe runs the benchmark function and updates the measure.
     fn observe<F: FnOnce(&P, &mut dyn FnMut())>(&mut self, f: F, params: &P);
+    /// Duration of the warm-up phase [`crate::Benchmark::benchmark`] should run, with all
+    /// timings discarded, before it starts collecting real measurements. Default: no warm-up.
+    fn warm_up(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// Formats a per-op time (in nanoseconds) with a unit appropriate for its magnitude.
+fn format_ns(ns: f64) -> String {
+    let secs = ns * 1e-9;
+    if secs < 1e-6 {
+        format!("{:.3} ns/op", secs * 1e9)
+    } else if secs < 1e-3 {
+        format!("{:.3} us/op", secs * 1e6)
+    } else if secs < 1.0 {
+        format!("{:.3} ms/op", secs * 1e3)
+    } else {
+        format!("{:.3} s/op", secs)
+    }
+}
+
+/// Returns the `p`-th percentile (0.0..=1.0) of an already-sorted slice.
+pub(crate) fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let idx = (((sorted.len() - 1) as f64) * p).round() as usize;
+    sorted[idx]
+}
+
+pub(crate) fn mean(samples: &[f64]) -> f64 {
+    samples.iter().sum::<f64>() / samples.len() as f64
+}
+
+/// A small xorshift64 PRNG, used to draw bootstrap resamples without pulling in a `rand`
+/// dependency. Not suitable for anything beyond resampling.
+struct Rng(u64);
+
+impl Rng {
+    fn new() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(1);
+        Self(seed | 1)
+    }
+    /// Returns a pseudo-random index in `0..bound`.
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 as usize) % bound
+    }
+}
+
+/// Draws `samples.len()` values uniformly with replacement from `samples` and returns their
+/// mean.
+fn resample_mean(samples: &[f64], rng: &mut Rng) -> f64 {
+    let sum: f64 = (0..samples.len())
+        .map(|_| samples[rng.below(samples.len())])
+        .sum();
+    sum / samples.len() as f64
+}
+
+/// The amount of work a single repetition of a benchmark performs, used to derive a throughput
+/// figure (bytes/s or elements/s) alongside the per-op time.
+#[derive(Debug, Clone, Copy)]
+pub enum Throughput {
+    /// Each repetition processes this many bytes.
+    Bytes(u64),
+    /// Each repetition processes this many elements.
+    Elements(u64),
+}
+
+impl Throughput {
+    /// Returns the throughput rate and its unit, given the time (in seconds) a single
+    /// repetition took.
+    fn per_sec(&self, secs_per_op: f64) -> (f64, &'static str) {
+        match self {
+            Throughput::Bytes(n) => (
+                *n as f64 / secs_per_op / (1024.0 * 1024.0 * 1024.0),
+                "GiB/s",
+            ),
+            Throughput::Elements(n) => (*n as f64 / secs_per_op / 1e6, "Melem/s"),
+        }
+    }
 }
 
 /// TimeMeasure measures the time it takes to run a benchmark, enforcing a minimum time.
 pub struct TimeMeasure {
     min_time: Duration,
+    warm_up: Duration,
+    throughput: Option<Throughput>,
     start: Instant,
     /// Time per repetition.
     pub time: Duration,
@@ -42,20 +127,28 @@ impl TimeMeasure {
             ..Default::default()
         }
     }
+    /// Runs the benchmark for at least `warm_up` before the real measurement loop starts,
+    /// discarding all timings from that phase. See [`Measure::warm_up`].
+    pub fn with_warm_up(mut self, warm_up: Duration) -> Self {
+        self.warm_up = warm_up;
+        self
+    }
+    /// Declares how much work a single repetition performs, so the `Display` impl can also
+    /// report a derived throughput (e.g. `X GiB/s` or `Y Melem/s`).
+    pub fn with_throughput(mut self, throughput: Throughput) -> Self {
+        self.throughput = Some(throughput);
+        self
+    }
 }
 
 impl Display for TimeMeasure {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let secs = self.time.as_secs_f64();
-        if secs < 1e-6 {
-            write!(f, "{:.3} ns/op", secs * 1e9)
-        } else if secs < 1e-3 {
-            write!(f, "{:.3} us/op", secs * 1e6)
-        } else if secs < 1.0 {
-            write!(f, "{:.3} ms/op", secs * 1e3)
-        } else {
-            write!(f, "{:.3} s/op", secs)
+        write!(f, "{}", format_ns(self.time.as_secs_f64() * 1e9))?;
+        if let Some(throughput) = self.throughput {
+            let (rate, unit) = throughput.per_sec(self.time.as_secs_f64());
+            write!(f, ", {:.3} {}", rate, unit)?;
         }
+        Ok(())
     }
 }
 
@@ -69,6 +162,9 @@ impl<P: Params> Measure<P> for TimeMeasure {
         self.stop();
         self.time /= params.nreps() as u32;
     }
+    fn warm_up(&self) -> Duration {
+        self.warm_up
+    }
 }
 
 impl TimeMeasure {
@@ -85,6 +181,8 @@ impl Default for TimeMeasure {
     fn default() -> Self {
         Self {
             min_time: Duration::from_secs(1),
+            warm_up: Duration::ZERO,
+            throughput: None,
             start: Instant::now(),
             time: Duration::default(),
             total_time: Duration::default(),
@@ -146,3 +244,346 @@ impl<Alloc: GlobalAlloc> Display for MemoryMeasure<Alloc> {
         )
     }
 }
+
+/// PeakMemoryMeasure measures the high-water mark of live (allocated but not yet freed) bytes
+/// during a benchmark repetition, which is the number that matters for memory-bound
+/// workloads, as opposed to [MemoryMeasure]'s cumulative allocation volume.
+///
+/// It takes a reference to a [CountingAllocator](crate::alloc::CountingAllocator).
+pub struct PeakMemoryMeasure<Alloc: GlobalAlloc + 'static> {
+    alloc: &'static CountingAllocator<Alloc>,
+    /// Peak live bytes observed during the repetition.
+    pub peak: usize,
+}
+
+impl<Alloc: GlobalAlloc> PeakMemoryMeasure<Alloc> {
+    /// Creates a new PeakMemoryMeasure.
+    pub fn new(alloc: &'static CountingAllocator<Alloc>) -> Self {
+        Self { alloc, peak: 0 }
+    }
+    fn start(&mut self) {
+        self.alloc.reset_peak();
+    }
+    fn stop(&mut self) {
+        self.peak = self
+            .alloc
+            .stats()
+            .peak
+            .load(std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+impl<Alloc: GlobalAlloc, P: Params> Measure<P> for PeakMemoryMeasure<Alloc> {
+    fn enough(&self, _params: &P) -> bool {
+        true
+    }
+    fn observe<F: FnOnce(&P, &mut dyn FnMut())>(&mut self, f: F, params: &P) {
+        self.start();
+        f(params, &mut || self.start());
+        self.stop();
+    }
+}
+
+impl<Alloc: GlobalAlloc> Display for PeakMemoryMeasure<Alloc> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} bytes peak live", self.peak)
+    }
+}
+
+/// Counts of samples classified as outliers by [Tukey's fences](https://en.wikipedia.org/wiki/Outlier#Tukey's_fences).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OutlierCounts {
+    /// Samples below `Q1 - 1.5*IQR` but not below `Q1 - 3*IQR`.
+    pub low_mild: usize,
+    /// Samples above `Q3 + 1.5*IQR` but not above `Q3 + 3*IQR`.
+    pub high_mild: usize,
+    /// Samples below `Q1 - 3*IQR`.
+    pub low_severe: usize,
+    /// Samples above `Q3 + 3*IQR`.
+    pub high_severe: usize,
+}
+
+impl OutlierCounts {
+    /// Total number of samples classified as outliers, mild or severe.
+    pub fn total(&self) -> usize {
+        self.low_mild + self.high_mild + self.low_severe + self.high_severe
+    }
+}
+
+struct TukeyFences {
+    mild_lo: f64,
+    mild_hi: f64,
+    severe_lo: f64,
+    severe_hi: f64,
+}
+
+/// Computes Tukey's fences from an already-sorted slice.
+fn tukey_fences(sorted: &[f64]) -> TukeyFences {
+    let q1 = percentile(sorted, 0.25);
+    let q3 = percentile(sorted, 0.75);
+    let iqr = q3 - q1;
+    TukeyFences {
+        mild_lo: q1 - 1.5 * iqr,
+        mild_hi: q3 + 1.5 * iqr,
+        severe_lo: q1 - 3.0 * iqr,
+        severe_hi: q3 + 3.0 * iqr,
+    }
+}
+
+/// StatsMeasure collects a sample set of per-iteration timings and reports a bootstrap
+/// confidence interval around their mean, instead of a single point estimate.
+///
+/// It gathers `sample_size` samples (one per `observe` call, in ns/op), then draws
+/// `nresamples` bootstrap resamples with replacement from that set, recomputing the mean for
+/// each resample. The `confidence_level`-percentile range of the resulting resample means is
+/// reported as the confidence interval.
+///
+/// It also classifies samples as outliers using Tukey's fences and reports a `clean_estimate`
+/// computed with severe outliers removed, so it's possible to see how much noise (from OS
+/// scheduling, interrupts, etc.) is distorting the headline estimate.
+pub struct StatsMeasure {
+    sample_size: usize,
+    nresamples: usize,
+    confidence_level: f64,
+    start: Instant,
+    /// Samples gathered so far, in ns/op.
+    pub samples: Vec<f64>,
+    /// Point estimate (mean of `samples`), in ns/op.
+    pub estimate: f64,
+    /// Lower bound of the bootstrap confidence interval, in ns/op.
+    pub ci_lo: f64,
+    /// Upper bound of the bootstrap confidence interval, in ns/op.
+    pub ci_hi: f64,
+    /// Counts of samples classified as mild/severe outliers.
+    pub outliers: OutlierCounts,
+    /// Mean of `samples` with severe outliers removed, in ns/op.
+    pub clean_estimate: f64,
+    /// Sorted bootstrap resample means backing `ci_lo`/`ci_hi`, kept around so a baseline
+    /// comparison (see [`crate::baseline`]) can test whether a later change is significant.
+    pub resamples: Vec<f64>,
+}
+
+impl StatsMeasure {
+    /// Creates a new StatsMeasure with the default 100 samples, 100,000 bootstrap resamples,
+    /// and a 95% confidence interval.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets the number of per-iteration samples to collect before the estimate is finalized.
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = sample_size;
+        self
+    }
+    /// Sets the number of bootstrap resamples drawn from the collected samples.
+    pub fn with_nresamples(mut self, nresamples: usize) -> Self {
+        self.nresamples = nresamples;
+        self
+    }
+    /// Sets the confidence level of the reported interval, e.g. `0.95` for a 95% CI.
+    pub fn with_confidence_level(mut self, confidence_level: f64) -> Self {
+        self.confidence_level = confidence_level;
+        self
+    }
+    fn start(&mut self) {
+        self.start = Instant::now();
+    }
+    fn analyze(&mut self) {
+        self.estimate = mean(&self.samples);
+        let mut rng = Rng::new();
+        let mut resamples: Vec<f64> = (0..self.nresamples)
+            .map(|_| resample_mean(&self.samples, &mut rng))
+            .collect();
+        resamples.sort_by(|a, b| a.total_cmp(b));
+        let alpha = 1.0 - self.confidence_level;
+        self.ci_lo = percentile(&resamples, alpha / 2.0);
+        self.ci_hi = percentile(&resamples, 1.0 - alpha / 2.0);
+        self.resamples = resamples;
+
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let fences = tukey_fences(&sorted);
+        let mut outliers = OutlierCounts::default();
+        let mut clean = Vec::with_capacity(self.samples.len());
+        for &s in &self.samples {
+            if s < fences.severe_lo {
+                outliers.low_severe += 1;
+            } else if s > fences.severe_hi {
+                outliers.high_severe += 1;
+            } else {
+                clean.push(s);
+                if s < fences.mild_lo {
+                    outliers.low_mild += 1;
+                } else if s > fences.mild_hi {
+                    outliers.high_mild += 1;
+                }
+            }
+        }
+        self.outliers = outliers;
+        self.clean_estimate = if clean.is_empty() {
+            self.estimate
+        } else {
+            mean(&clean)
+        };
+    }
+}
+
+impl Default for StatsMeasure {
+    fn default() -> Self {
+        Self {
+            sample_size: 100,
+            nresamples: 100_000,
+            confidence_level: 0.95,
+            start: Instant::now(),
+            samples: Vec::new(),
+            estimate: 0.0,
+            ci_lo: 0.0,
+            ci_hi: 0.0,
+            outliers: OutlierCounts::default(),
+            clean_estimate: 0.0,
+            resamples: Vec::new(),
+        }
+    }
+}
+
+impl<P: Params> Measure<P> for StatsMeasure {
+    fn enough(&self, _params: &P) -> bool {
+        self.samples.len() >= self.sample_size
+    }
+    fn observe<F: FnOnce(&P, &mut dyn FnMut())>(&mut self, f: F, params: &P) {
+        self.start();
+        f(params, &mut || self.start());
+        let elapsed = self.start.elapsed();
+        self.samples
+            .push(elapsed.as_secs_f64() * 1e9 / params.nreps() as f64);
+        if self.enough(params) {
+            self.analyze();
+        }
+    }
+}
+
+impl Display for StatsMeasure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} [{} {}]",
+            format_ns(self.estimate),
+            format_ns(self.ci_lo),
+            format_ns(self.ci_hi)
+        )?;
+        if self.outliers.total() > 0 {
+            write!(
+                f,
+                ", {} outliers among {} samples (low-mild {}, high-mild {}, low-severe {}, high-severe {}); clean estimate {}",
+                self.outliers.total(),
+                self.samples.len(),
+                self.outliers.low_mild,
+                self.outliers.high_mild,
+                self.outliers.low_severe,
+                self.outliers.high_severe,
+                format_ns(self.clean_estimate)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Both drives two inner measures from a single `observe` call, so e.g. time and memory can be
+/// captured together in one benchmark pass instead of running the target function twice.
+///
+/// `enough()` returns true only once both inner measures are satisfied, and `Display` prints
+/// both results.
+pub struct Both<A, B> {
+    /// First inner measure.
+    pub a: A,
+    /// Second inner measure.
+    pub b: B,
+}
+
+impl<A, B> Both<A, B> {
+    /// Creates a new composite measure driving `a` and `b` together.
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<P: Params, A: Measure<P>, B: Measure<P>> Measure<P> for Both<A, B> {
+    fn enough(&self, params: &P) -> bool {
+        self.a.enough(params) && self.b.enough(params)
+    }
+    fn observe<F: FnOnce(&P, &mut dyn FnMut())>(&mut self, f: F, params: &P) {
+        let b = &mut self.b;
+        self.a.observe(
+            move |p: &P, reset_a: &mut dyn FnMut()| {
+                b.observe(
+                    move |p2: &P, reset_b: &mut dyn FnMut()| {
+                        f(p2, &mut || {
+                            reset_a();
+                            reset_b();
+                        });
+                    },
+                    p,
+                );
+            },
+            params,
+        );
+    }
+    fn warm_up(&self) -> Duration {
+        self.a.warm_up().max(self.b.warm_up())
+    }
+}
+
+impl<A: Display, B: Display> Display for Both<A, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {}", self.a, self.b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_bounds_on_fixed_data() {
+        let sorted = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 1.0), 5.0);
+        assert_eq!(percentile(&sorted, 0.5), 3.0);
+    }
+
+    #[test]
+    fn bootstrap_ci_is_exact_for_constant_samples() {
+        let mut measure = StatsMeasure::new()
+            .with_sample_size(5)
+            .with_nresamples(1_000);
+        measure.samples = vec![10.0; 5];
+        measure.analyze();
+        assert_eq!(measure.estimate, 10.0);
+        assert_eq!(measure.ci_lo, 10.0);
+        assert_eq!(measure.ci_hi, 10.0);
+    }
+
+    #[test]
+    fn bootstrap_ci_brackets_the_estimate() {
+        let mut measure = StatsMeasure::new()
+            .with_sample_size(6)
+            .with_nresamples(1_000);
+        measure.samples = vec![8.0, 9.0, 10.0, 10.0, 11.0, 12.0];
+        measure.analyze();
+        assert!(measure.ci_lo <= measure.estimate);
+        assert!(measure.estimate <= measure.ci_hi);
+    }
+
+    #[test]
+    fn tukey_fences_flag_a_crafted_outlier() {
+        // A tight cluster at 10 plus one wildly inflated sample.
+        let mut measure = StatsMeasure::new()
+            .with_sample_size(9)
+            .with_nresamples(100);
+        measure.samples = vec![10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 1000.0];
+        measure.analyze();
+        assert_eq!(measure.outliers.high_severe, 1);
+        assert_eq!(measure.outliers.total(), 1);
+        // The clean estimate should be unaffected by the outlier.
+        assert_eq!(measure.clean_estimate, 10.0);
+    }
+}